@@ -0,0 +1,89 @@
+use std::io;
+use memsec::memzero;
+use bytes::Bytes;
+
+
+/// Incremental builder for a locked [`Bytes`](struct.Bytes.html).
+///
+/// Assembling a secret from several pieces -- KDF output concatenated with a
+/// salt, a multi-part wire message -- normally means building it up in an
+/// ordinary growable buffer and only locking the result afterward, so the
+/// plaintext sits unprotected the whole time it's being assembled.
+/// `SecBytesBuilder` keeps its backing store `mlock`ed from the start and
+/// implements `std::io::Write`, so it can be filled with the usual `Write`
+/// plumbing (including `byteorder::WriteBytesExt`, which is a blanket impl
+/// over any `Write`). Whenever it has to grow, the old allocation is zeroed
+/// before it's dropped.
+pub struct SecBytesBuilder {
+    bytes: Bytes,
+    len: usize
+}
+
+impl SecBytesBuilder {
+    /// ```
+    /// use std::io::Write;
+    /// use seckey::SecBytesBuilder;
+    ///
+    /// let mut builder = SecBytesBuilder::with_capacity(4);
+    /// builder.write_all(b"sec").unwrap();
+    /// builder.write_all(b"ret").unwrap();
+    /// assert_eq!(b"secret", &*builder.finish());
+    /// ```
+    pub fn with_capacity(cap: usize) -> SecBytesBuilder {
+        SecBytesBuilder {
+            bytes: Bytes::from(vec![0; cap]),
+            len: 0
+        }
+    }
+
+    /// Number of bytes written so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn ensure_capacity(&mut self, additional: usize) {
+        let required = self.len + additional;
+        if required <= self.bytes.len() {
+            return;
+        }
+
+        let new_cap = required.next_power_of_two().max(16);
+        let mut grown = Bytes::from(vec![0; new_cap]);
+        grown[..self.len].copy_from_slice(&self.bytes[..self.len]);
+
+        unsafe { memzero(self.bytes.as_mut_ptr(), self.bytes.len()) };
+        self.bytes = grown;
+    }
+
+    /// Finalize the builder into a right-sized, still-locked `Bytes`.
+    pub fn finish(self) -> Bytes {
+        let SecBytesBuilder { mut bytes, len } = self;
+        if len == bytes.len() {
+            return bytes;
+        }
+
+        let out = Bytes::new(&bytes[..len]);
+        unsafe { memzero(bytes.as_mut_ptr(), bytes.len()) };
+        out
+    }
+}
+
+impl io::Write for SecBytesBuilder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.ensure_capacity(buf.len());
+        let start = self.len;
+        self.bytes[start..start + buf.len()].copy_from_slice(buf);
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt;
 use std::iter::repeat;
 use std::ops::{ Deref, DerefMut };
 use memsec::{ memeq, mlock, munlock };
+use rand::{ CryptoRng, RngCore };
 
 
 /// Temporary Bytes.
@@ -29,6 +30,24 @@ impl Bytes {
     pub fn empty() -> Bytes {
         Bytes(Vec::new())
     }
+
+    /// Generate `len` random bytes directly into locked memory.
+    ///
+    /// The destination is `mlock`ed before `rng` ever touches it, so the
+    /// generated bytes never pass through an unlocked buffer.
+    ///
+    /// ```
+    /// use seckey::Bytes;
+    /// use rand::rngs::OsRng;
+    ///
+    /// let bytes = Bytes::random(8, &mut OsRng);
+    /// assert_eq!(8, bytes.len());
+    /// ```
+    pub fn random<R: RngCore + CryptoRng>(len: usize, rng: &mut R) -> Bytes {
+        let mut bytes = Bytes::from(vec![0; len]);
+        rng.fill_bytes(&mut bytes);
+        bytes
+    }
 }
 
 impl From<Vec<u8>> for Bytes {
@@ -101,3 +120,67 @@ impl Drop for Bytes {
         unsafe { munlock(self.0.as_mut_ptr(), self.0.len()) };
     }
 }
+
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::fmt;
+    use serde::{ Serialize, Serializer, Deserialize, Deserializer };
+    use serde::de::{ self, Visitor, SeqAccess };
+    use super::Bytes;
+
+    impl Serialize for Bytes {
+        /// Emits the locked bytes directly; no intermediate unlocked copy
+        /// is made on this side of the call.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self)
+        }
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Bytes;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a byte sequence")
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Bytes, E> {
+            let mut bytes = Bytes::from(vec![0; v.len()]);
+            bytes.copy_from_slice(v);
+            Ok(bytes)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bytes, A::Error> {
+            let mut bytes = Bytes::from(vec![0; seq.size_hint().unwrap_or(0)]);
+            let mut len = 0;
+
+            while let Some(byte) = seq.next_element()? {
+                if len == bytes.len() {
+                    let mut grown = Bytes::from(vec![0; (len + 1).next_power_of_two()]);
+                    grown[..len].copy_from_slice(&bytes[..len]);
+                    bytes = grown;
+                }
+                bytes[len] = byte;
+                len += 1;
+            }
+
+            if len == bytes.len() {
+                return Ok(bytes);
+            }
+            let mut out = Bytes::from(vec![0; len]);
+            out.copy_from_slice(&bytes[..len]);
+            Ok(out)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Bytes {
+        /// Allocates and `mlock`s the destination first, then fills it in
+        /// place from the visitor -- the plaintext is never copied through
+        /// an unlocked intermediate `Vec`.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::{ mem, ptr, slice };
+use std::marker::PhantomData;
+use std::ops::Deref;
+use memsec::{ memzero, malloc, free, mprotect, Prot };
+use rand::RngCore;
+use rand::rngs::OsRng;
+use seckey::SecKey;
+
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 8;
+const KEY_WORDS: usize = KEY_LEN / 8;
+
+/// A deterministic mixing function (SplitMix64) used to turn state into the
+/// next keystream block. Must be a pure function of its inputs: `new()`
+/// encrypts and `read()` decrypts with the same `(key, nonce)`, and the two
+/// have to agree on every block or decryption never inverts.
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A keyed XOR-with-counter keystream. Not an audited cipher, just enough
+/// to keep `EncSecKey`'s at-rest bytes out of plain sight between borrows.
+/// Every word of the key is mixed into every block (instead of folding the
+/// key down to a single 64-bit seed up front), so the full `KEY_LEN` of key
+/// material feeds the keystream rather than just 64 bits of it.
+fn keystream(key: &[u8; KEY_LEN], nonce: &[u8; NONCE_LEN], out: &mut [u8]) {
+    let mut key_words = [0u64; KEY_WORDS];
+    for (word, chunk) in key_words.iter_mut().zip(key.chunks(8)) {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(chunk);
+        *word = u64::from_ne_bytes(buf);
+    }
+    let mut nonce_buf = [0u8; 8];
+    nonce_buf.copy_from_slice(nonce);
+    let nonce_word = u64::from_ne_bytes(nonce_buf);
+
+    for (counter, chunk) in out.chunks_mut(8).enumerate() {
+        let mut state = nonce_word ^ counter as u64;
+        for word in &key_words {
+            state = splitmix64(state ^ word);
+        }
+        let block = state.to_ne_bytes();
+        for (b, k) in chunk.iter_mut().zip(block.iter()) {
+            *b ^= *k;
+        }
+    }
+}
+
+
+/// Encrypted Secure Key.
+///
+/// Unlike [`SecKey`](struct.SecKey.html), which keeps its plaintext behind a
+/// `mprotect(NoAccess)` page for the whole lifetime of the value, `EncSecKey<T>`
+/// keeps only ciphertext in its main allocation and decrypts into a short-lived
+/// protected page for the span of a [`read`](#method.read) borrow. This shrinks
+/// the plaintext-exposure window from "as long as the process lives" to "as long
+/// as the guard is held".
+///
+/// `T` is bound to `Copy`: every `read()` reconstructs a fresh, independent
+/// bitwise copy of the stored value into a new page, so `T` must not own a
+/// resource (a `Vec`, a `Box`, a file handle) that a destructor would try to
+/// free more than once.
+pub struct EncSecKey<T> {
+    key: SecKey<[u8; KEY_LEN + NONCE_LEN]>,
+    ciphertext: Vec<u8>,
+    _marker: PhantomData<T>
+}
+
+impl<T> EncSecKey<T> where T: Copy {
+    /// ```
+    /// use seckey::EncSecKey;
+    ///
+    /// let k = EncSecKey::new([1]).unwrap();
+    /// assert_eq!([1], *k.read());
+    /// assert_eq!([1], *k.read());
+    /// ```
+    pub fn new(mut t: T) -> Result<EncSecKey<T>, T> {
+        let mut key_nonce = [0u8; KEY_LEN + NONCE_LEN];
+        OsRng.fill_bytes(&mut key_nonce);
+
+        let len = mem::size_of::<T>();
+        let mut keystream_buf = vec![0u8; len];
+        {
+            let (key_bytes, nonce_bytes) = split_key_nonce(&key_nonce);
+            keystream(key_bytes, nonce_bytes, &mut keystream_buf);
+        }
+
+        let key = match SecKey::new(key_nonce) {
+            Ok(key) => key,
+            Err(_) => {
+                unsafe { memzero(keystream_buf.as_mut_ptr(), len) };
+                return Err(t);
+            }
+        };
+
+        // XOR straight into the ciphertext Vec: it only ever holds the
+        // encrypted result, never the raw bytes of `t`.
+        let t_bytes = unsafe { slice::from_raw_parts(&t as *const T as *const u8, len) };
+        let ciphertext: Vec<u8> = t_bytes.iter().zip(keystream_buf.iter()).map(|(a, b)| a ^ b).collect();
+
+        unsafe {
+            memzero(&mut t, len);
+            memzero(keystream_buf.as_mut_ptr(), len);
+        }
+
+        Ok(EncSecKey { key, ciphertext, _marker: PhantomData })
+    }
+
+    /// Borrow Read.
+    ///
+    /// Decrypts straight into a freshly `malloc`'d, `mprotect`ed page: the
+    /// ciphertext is copied in (not sensitive on its own), then XORed in
+    /// place, so the plaintext only ever exists inside that protected
+    /// allocation. It's zeroed and freed when the guard is dropped.
+    pub fn read(&self) -> EncSecReadGuard<T> {
+        let len = mem::size_of::<T>();
+
+        let memptr: *mut u8 = unsafe { malloc(len) }
+            .unwrap_or_else(|| panic!("memsec::malloc fail: {}", len));
+
+        unsafe { ptr::copy_nonoverlapping(self.ciphertext.as_ptr(), memptr, len) };
+
+        {
+            let key_nonce = self.key.read();
+            let (key_bytes, nonce_bytes) = split_key_nonce(&key_nonce);
+            let plaintext = unsafe { slice::from_raw_parts_mut(memptr, len) };
+            keystream(key_bytes, nonce_bytes, plaintext);
+        }
+
+        let memptr = memptr as *mut T;
+        unsafe { mprotect(memptr, Prot::ReadOnly) };
+
+        EncSecReadGuard { ptr: memptr, _marker: PhantomData }
+    }
+}
+
+fn split_key_nonce(key_nonce: &[u8; KEY_LEN + NONCE_LEN]) -> (&[u8; KEY_LEN], &[u8; NONCE_LEN]) {
+    unsafe {
+        let key = &*(key_nonce[..KEY_LEN].as_ptr() as *const [u8; KEY_LEN]);
+        let nonce = &*(key_nonce[KEY_LEN..].as_ptr() as *const [u8; NONCE_LEN]);
+        (key, nonce)
+    }
+}
+
+impl<T> Drop for EncSecKey<T> {
+    fn drop(&mut self) {
+        unsafe { memzero(self.ciphertext.as_mut_ptr(), self.ciphertext.len()) };
+    }
+}
+
+
+/// Read Guard for [`EncSecKey`](struct.EncSecKey.html).
+pub struct EncSecReadGuard<'a, T: 'a> {
+    ptr: *mut T,
+    _marker: PhantomData<&'a EncSecKey<T>>
+}
+
+impl<'a, T: 'a> Deref for EncSecReadGuard<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.ptr }
+    }
+}
+
+impl<'a, T: 'a> Drop for EncSecReadGuard<'a, T> {
+    /// `T: Copy` guarantees this page's value has no drop glue to run; a
+    /// non-`Copy` `T` with ownership semantics would see every `read()`
+    /// recreate the same bytes, so running its destructor here would
+    /// double-free whatever it owns. Zero and free the page and stop there.
+    fn drop(&mut self) {
+        unsafe {
+            mprotect(self.ptr, Prot::ReadWrite);
+            memzero(self.ptr, mem::size_of::<T>());
+            free(self.ptr);
+        }
+    }
+}
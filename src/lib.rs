@@ -0,0 +1,22 @@
+//! # Seckey
+//!
+//! Secure key and bytes protection in memory.
+//!
+//! More docs see [Secure memory · libsodium](https://download.libsodium.org/doc/helpers/memory_management.html).
+
+extern crate memsec;
+extern crate rand;
+#[cfg(feature = "serde")] extern crate serde;
+
+mod util;
+mod seckey;
+mod bytes;
+mod enc_seckey;
+mod sec_vec;
+mod builder;
+
+pub use seckey::{ SecKey, SecReadGuard, SecWriteGuard };
+pub use bytes::Bytes;
+pub use enc_seckey::{ EncSecKey, EncSecReadGuard };
+pub use sec_vec::{ SecVec, SecVecReadGuard, SecVecWriteGuard };
+pub use builder::SecBytesBuilder;
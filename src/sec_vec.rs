@@ -0,0 +1,263 @@
+use std::{ fmt, mem, process, ptr };
+use std::ops::{ Deref, DerefMut };
+use std::sync::{ Mutex, Condvar };
+use memsec::{ memzero, malloc, free, mprotect, Prot };
+use util::fill_random;
+
+
+const CANARY_LEN: usize = mem::size_of::<usize>();
+
+/// Dynamically-sized Secure Bytes.
+///
+/// [`SecKey<T>`](struct.SecKey.html) only protects `Sized` values whose length
+/// is known at compile time; `SecVec` is the run-time-length equivalent for
+/// passwords, tokens, and anything else whose size isn't known until it's
+/// read off the wire. The region is `memsec::malloc`ed with the same guard
+/// pages and `mprotect(NoAccess)` default as `SecKey`, plus a random canary
+/// word kept just inside the trailing guard; if that canary doesn't match on
+/// drop, a buffer overflow/underflow wrote past the end of the secret and we
+/// `abort()` rather than free a possibly corrupted allocation.
+pub struct SecVec {
+    ptr: *mut u8,
+    len: usize,
+    canary: usize,
+    count: Mutex<isize>,
+    cond: Condvar
+}
+
+unsafe impl Send for SecVec {}
+unsafe impl Sync for SecVec {}
+
+impl SecVec {
+    /// ```
+    /// use seckey::SecVec;
+    ///
+    /// let v = SecVec::new(&[1, 2, 3]).unwrap();
+    /// assert_eq!([1, 2, 3], *v.read());
+    /// ```
+    pub fn new(bytes: &[u8]) -> Option<SecVec> {
+        let len = bytes.len();
+        let mut canary_bytes = [0u8; CANARY_LEN];
+        fill_random(&mut canary_bytes);
+        let canary = usize::from_ne_bytes(canary_bytes);
+
+        let memptr: *mut u8 = unsafe { malloc(len + CANARY_LEN)? };
+
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), memptr, len);
+            ptr::write(memptr.add(len) as *mut usize, canary);
+            mprotect(memptr, Prot::NoAccess);
+        }
+
+        Some(SecVec { ptr: memptr, len, canary, count: Mutex::new(0), cond: Condvar::new() })
+    }
+
+    /// Create a zeroed `SecVec` of the given length.
+    #[inline]
+    pub fn zero(len: usize) -> Option<SecVec> {
+        SecVec::new(&vec![0; len])
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn check_canary(&self) {
+        let stored = unsafe { ptr::read(self.ptr.add(self.len) as *const usize) };
+        if stored != self.canary {
+            // The trailing guard's canary is gone: something wrote past the
+            // end of this secret. Don't try to recover, just die loudly.
+            process::abort();
+        }
+    }
+
+    /// Acquire a read slot, blocking while a write borrow is outstanding.
+    /// The count bump and the 0 -> 1 `mprotect(ReadOnly)` transition happen
+    /// under the same lock, so no other thread can ever observe one without
+    /// the other.
+    fn read_unlock(&self) {
+        let mut count = self.count.lock().unwrap();
+        while *count < 0 {
+            count = self.cond.wait(count).unwrap();
+        }
+        if *count == 0 {
+            unsafe { mprotect(self.ptr, Prot::ReadOnly) };
+        }
+        *count += 1;
+    }
+
+    fn read_lock(&self) {
+        let mut count = self.count.lock().unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.check_canary();
+            unsafe { mprotect(self.ptr, Prot::NoAccess) };
+            self.cond.notify_all();
+        }
+    }
+
+    /// Try to acquire the sole write slot; fails if any read or write
+    /// borrow is outstanding. The check and the `mprotect(ReadWrite)`
+    /// transition happen under the same lock as every other transition.
+    fn try_write_unlock(&self) -> bool {
+        let mut count = self.count.lock().unwrap();
+        if *count == 0 {
+            *count = -1;
+            unsafe { mprotect(self.ptr, Prot::ReadWrite) };
+            true
+        } else {
+            false
+        }
+    }
+
+    fn write_lock(&self) {
+        let mut count = self.count.lock().unwrap();
+        *count = 0;
+        self.check_canary();
+        unsafe { mprotect(self.ptr, Prot::NoAccess) };
+        self.cond.notify_all();
+    }
+
+    /// Borrow Read.
+    #[inline]
+    pub fn read(&self) -> SecVecReadGuard {
+        self.read_unlock();
+        SecVecReadGuard(self)
+    }
+
+    /// Borrow Write.
+    ///
+    /// Returns `None` if another read or write borrow is outstanding.
+    #[inline]
+    pub fn write(&self) -> Option<SecVecWriteGuard> {
+        if self.try_write_unlock() {
+            Some(SecVecWriteGuard(self))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Debug for SecVec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "** sec vec ({}) **", self.len)
+    }
+}
+
+impl Drop for SecVec {
+    fn drop(&mut self) {
+        unsafe {
+            mprotect(self.ptr, Prot::ReadWrite);
+            self.check_canary();
+            memzero(self.ptr, self.len + CANARY_LEN);
+            free(self.ptr);
+        }
+    }
+}
+
+
+/// Read Guard for [`SecVec`](struct.SecVec.html).
+pub struct SecVecReadGuard<'a>(&'a SecVec);
+
+impl<'a> Deref for SecVecReadGuard<'a> {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.0.ptr, self.0.len) }
+    }
+}
+
+impl<'a> Drop for SecVecReadGuard<'a> {
+    fn drop(&mut self) {
+        self.0.read_lock();
+    }
+}
+
+
+/// Write Guard for [`SecVec`](struct.SecVec.html).
+pub struct SecVecWriteGuard<'a>(&'a SecVec);
+
+impl<'a> Deref for SecVecWriteGuard<'a> {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.0.ptr, self.0.len) }
+    }
+}
+
+impl<'a> DerefMut for SecVecWriteGuard<'a> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.0.ptr, self.0.len) }
+    }
+}
+
+impl<'a> Drop for SecVecWriteGuard<'a> {
+    fn drop(&mut self) {
+        self.0.write_lock();
+    }
+}
+
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::fmt;
+    use serde::{ Serialize, Serializer, Deserialize, Deserializer };
+    use serde::de::{ self, Visitor, SeqAccess };
+    use super::SecVec;
+
+    impl Serialize for SecVec {
+        /// Holds the region `ReadOnly` only for the span of the call.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.read())
+        }
+    }
+
+    struct SecVecVisitor;
+
+    impl<'de> Visitor<'de> for SecVecVisitor {
+        type Value = SecVec;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a byte sequence")
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<SecVec, E> {
+            SecVec::new(v).ok_or_else(|| E::custom("memsec::malloc failed"))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<SecVec, A::Error> {
+            // Accumulate into an `mlock`ed `Bytes` rather than a plain `Vec`,
+            // so the plaintext stays locked while its final length is unknown.
+            use bytes::Bytes;
+
+            let mut bytes = Bytes::from(vec![0; seq.size_hint().unwrap_or(0)]);
+            let mut len = 0;
+
+            while let Some(byte) = seq.next_element()? {
+                if len == bytes.len() {
+                    let mut grown = Bytes::from(vec![0; (len + 1).next_power_of_two()]);
+                    grown[..len].copy_from_slice(&bytes[..len]);
+                    bytes = grown;
+                }
+                bytes[len] = byte;
+                len += 1;
+            }
+
+            SecVec::new(&bytes[..len]).ok_or_else(|| de::Error::custom("memsec::malloc failed"))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for SecVec {
+        /// A byte string is copied straight into the freshly allocated,
+        /// `mlock`ed destination. A byte sequence that arrives element by
+        /// element is first gathered in an `mlock`ed `Bytes` buffer and then
+        /// copied into the destination.
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<SecVec, D::Error> {
+            deserializer.deserialize_bytes(SecVecVisitor)
+        }
+    }
+}
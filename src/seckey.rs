@@ -1,7 +1,8 @@
 use std::{ fmt, mem, ptr };
 use std::ops::{ Deref, DerefMut };
-use std::cell::Cell;
+use std::sync::{ Mutex, Condvar };
 use memsec::{ memzero, malloc, free, mprotect, Prot };
+use rand::{ CryptoRng, RngCore };
 
 
 /// Secure Key.
@@ -9,12 +10,23 @@ use memsec::{ memzero, malloc, free, mprotect, Prot };
 /// The use [memsec/malloc](../../memsec/fn.malloc.html) protection secret bytes.
 /// When you need the password stored in the memory, you should use it.
 ///
+/// The borrow count (positive while read-borrowed, negative while
+/// write-borrowed) lives behind a `Mutex`, and every `ReadOnly`/`ReadWrite`/
+/// `NoAccess` page transition happens while that mutex is held, so the count
+/// update and the `mprotect` call can never be observed out of sync by
+/// another thread. This is what makes `SecKey<T>` `Send`/`Sync` and safely
+/// shareable behind an `Arc`.
+///
 /// More docs see [Secure memory · libsodium](https://download.libsodium.org/doc/helpers/memory_management.html).
 pub struct SecKey<T> {
     ptr: *mut T,
-    count: Cell<usize>
+    count: Mutex<isize>,
+    cond: Condvar
 }
 
+unsafe impl<T> Send for SecKey<T> where T: Send {}
+unsafe impl<T> Sync for SecKey<T> where T: Send + Sync {}
+
 impl<T> Default for SecKey<T> where T: Default {
     fn default() -> Self {
         SecKey::new(T::default())
@@ -60,36 +72,83 @@ impl<T> SecKey<T> where T: Sized {
 
         Some(SecKey {
             ptr: memptr,
-            count: Cell::new(0)
+            count: Mutex::new(0),
+            cond: Condvar::new()
         })
     }
 }
 
+impl<T> SecKey<T> where T: Default + AsMut<[u8]> {
+    /// Generate a secret directly into protected memory.
+    ///
+    /// Unlike [`new`](#method.new), the value never exists in an unlocked
+    /// buffer: a `T::default()` placeholder is moved into the protected page
+    /// as usual, then `rng` fills it in place through a write borrow, so the
+    /// freshly generated bytes only ever live behind `mprotect`.
+    ///
+    /// ```
+    /// use seckey::SecKey;
+    /// use rand::rngs::OsRng;
+    ///
+    /// let k = SecKey::<[u8; 16]>::random(&mut OsRng).unwrap();
+    /// assert_eq!(16, k.read().len());
+    /// ```
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Result<SecKey<T>, T> {
+        let key = SecKey::new(T::default())?;
+        {
+            let mut w = key.write().expect("freshly created SecKey has no other borrows");
+            rng.fill_bytes(w.as_mut());
+        }
+        Ok(key)
+    }
+}
+
 impl<T> SecKey<T> {
+    /// Acquire a read slot, blocking while a write borrow is outstanding.
+    /// Positive counts are concurrent readers; the count bump and the 0 -> 1
+    /// `mprotect(ReadOnly)` transition happen under the same lock, so no
+    /// other thread can ever observe one without the other.
     fn read_unlock(&self) {
-        let count = self.count.get();
-        self.count.set(count + 1);
-        if count == 0 {
+        let mut count = self.count.lock().unwrap();
+        while *count < 0 {
+            count = self.cond.wait(count).unwrap();
+        }
+        if *count == 0 {
             unsafe { mprotect(self.ptr, Prot::ReadOnly) };
         }
+        *count += 1;
     }
 
-    fn write_unlock(&self) {
-        let count = self.count.get();
-        self.count.set(count + 1);
-        if count == 0 {
-            unsafe { mprotect(self.ptr, Prot::ReadWrite) };
+    fn read_lock(&self) {
+        let mut count = self.count.lock().unwrap();
+        *count -= 1;
+        if *count == 0 {
+            unsafe { mprotect(self.ptr, Prot::NoAccess) };
+            self.cond.notify_all();
         }
     }
 
-    fn lock(&self) {
-        let count = self.count.get();
-        self.count.set(count - 1);
-        if count <= 1 {
-            unsafe { mprotect(self.ptr, Prot::NoAccess) };
+    /// Try to acquire the sole write slot; fails if any read or write
+    /// borrow is outstanding. The check and the `mprotect(ReadWrite)`
+    /// transition happen under the same lock as every other transition.
+    fn try_write_unlock(&self) -> bool {
+        let mut count = self.count.lock().unwrap();
+        if *count == 0 {
+            *count = -1;
+            unsafe { mprotect(self.ptr, Prot::ReadWrite) };
+            true
+        } else {
+            false
         }
     }
 
+    fn write_lock(&self) {
+        let mut count = self.count.lock().unwrap();
+        *count = 0;
+        unsafe { mprotect(self.ptr, Prot::NoAccess) };
+        self.cond.notify_all();
+    }
+
     /// Borrow Read.
     ///
     /// ```
@@ -106,24 +165,32 @@ impl<T> SecKey<T> {
 
     /// Borrow Write.
     ///
+    /// Returns `None` if another read or write borrow is already outstanding,
+    /// which is what lets many threads hold an `Arc<SecKey<T>>` at once: the
+    /// page permission model (many readers OR one writer) is enforced by the
+    /// mutex-guarded borrow count instead of by the borrow checker.
+    ///
     /// ```
     /// # use seckey::SecKey;
     /// #
-    /// # let mut secpass = SecKey::new([8u8; 8]).unwrap();
-    /// let mut wpass = secpass.write();
+    /// # let secpass = SecKey::new([8u8; 8]).unwrap();
+    /// let mut wpass = secpass.write().unwrap();
     /// wpass[0] = 0;
     /// assert_eq!([0, 8, 8, 8, 8, 8, 8, 8], *wpass);
     /// ```
     #[inline]
-    pub fn write(&mut self) -> SecWriteGuard<T> {
-        self.write_unlock();
-        SecWriteGuard(self)
+    pub fn write(&self) -> Option<SecWriteGuard<T>> {
+        if self.try_write_unlock() {
+            Some(SecWriteGuard(self))
+        } else {
+            None
+        }
     }
 }
 
 impl<T> fmt::Debug for SecKey<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "** sec key ({}) **", self.count.get())
+        write!(f, "** sec key ({}) **", *self.count.lock().unwrap())
     }
 }
 
@@ -156,13 +223,13 @@ impl<'a, T: 'a> Deref for SecReadGuard<'a, T> {
 
 impl<'a, T: 'a> Drop for SecReadGuard<'a, T> {
     fn drop(&mut self) {
-        self.0.lock();
+        self.0.read_lock();
     }
 }
 
 
 /// Write Guard.
-pub struct SecWriteGuard<'a, T: 'a>(&'a mut SecKey<T>);
+pub struct SecWriteGuard<'a, T: 'a>(&'a SecKey<T>);
 
 impl<'a, T: 'a> Deref for SecWriteGuard<'a, T> {
     type Target = T;
@@ -179,6 +246,6 @@ impl<'a, T: 'a> DerefMut for SecWriteGuard<'a, T> {
 
 impl<'a, T: 'a> Drop for SecWriteGuard<'a, T> {
     fn drop(&mut self) {
-        self.0.lock();
+        self.0.write_lock();
     }
 }
@@ -0,0 +1,18 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{ BuildHasher, Hasher };
+
+
+/// Fill `buf` with bytes drawn from the OS-seeded randomness that
+/// `std::collections::hash_map::RandomState` already pulls in to defend
+/// `HashMap` against hash-flooding. This is a best-effort source, used for
+/// `SecVec`'s canary word; [`SecKey::random`]
+/// (struct.SecKey.html#method.random) is the place to plug in a real
+/// `CryptoRng` when the caller has one.
+pub fn fill_random(buf: &mut [u8]) {
+    for chunk in buf.chunks_mut(8) {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(chunk.len());
+        let bytes = hasher.finish().to_ne_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
@@ -0,0 +1,34 @@
+extern crate seckey;
+#[cfg(unix)] extern crate nix;
+
+use std::ptr;
+use seckey::SecVec;
+
+
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "ios"))))]
+#[should_panic]
+#[test]
+fn sec_vec_canary_abort_test() {
+    use nix::sys::signal;
+    extern fn sigabrt(_: i32) { panic!() }
+    let sigaction = signal::SigAction::new(
+        signal::SigHandler::Handler(sigabrt),
+        signal::SA_SIGINFO,
+        signal::SigSet::empty(),
+    );
+    unsafe { signal::sigaction(signal::SIGABRT, &sigaction).ok() };
+
+    let v = SecVec::new(&[1, 2, 3]).unwrap();
+    {
+        let mut w = v.write().unwrap();
+        let len = w.len();
+        // Write past the end of the accessible region into the trailing
+        // canary word, simulating a buffer overflow. Flipping the byte's bits
+        // guarantees it differs from the random canary.
+        unsafe {
+            let p = w.as_mut_ptr().add(len);
+            ptr::write(p, !ptr::read(p));
+        }
+    } // releasing the write guard checks the canary and `abort()`s -> SIGABRT
+    drop(v);
+}
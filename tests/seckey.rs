@@ -18,11 +18,32 @@ fn protect_seckey_test() {
     );
     unsafe { signal::sigaction(signal::SIGSEGV, &sigaction).ok() };
 
-    let mut secpass = SecKey::new(&[1; 8]).unwrap();
+    let secpass = SecKey::new(&[1; 8]).unwrap();
 
-    let mut wpass = secpass.write();
+    let mut wpass = secpass.write().unwrap();
     let (bs_ptr, bs_len) = (wpass.as_mut_ptr(), wpass.len()); // violence get secpass ptr
     let bs_bytes = unsafe { slice::from_raw_parts_mut(bs_ptr, bs_len) };
     drop(wpass);
     bs_bytes[0] = 0; // SIGSEGV !
 }
+
+#[test]
+fn arc_seckey_concurrent_read_test() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let secpass = Arc::new(SecKey::new([1u8; 8]).unwrap());
+
+    let handles: Vec<_> = (0..4).map(|_| {
+        let secpass = Arc::clone(&secpass);
+        thread::spawn(move || {
+            for _ in 0..100 {
+                assert_eq!([1u8; 8], *secpass.read());
+            }
+        })
+    }).collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}